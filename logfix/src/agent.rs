@@ -0,0 +1,105 @@
+use crate::providers::{Message, Provider};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_TOOL_ITERATIONS: usize = 8;
+
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+    tool: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+pub const TOOL_INSTRUCTIONS: &str = "You have three tools available against the log file: \
+`grep_log` (args: `pattern`) returns every line matching a regex, `get_context` (args: `line_no`, `radius`) \
+returns the lines around `line_no`, and `read_file` (args: `path`) returns another file's contents \
+(restricted to the log file's own directory). \
+To call one, reply with ONLY JSON shaped as `{\"tool\": \"grep_log\", \"args\": {\"pattern\": \"...\"}}` \
+and nothing else. Once you have enough context, reply with your final answer as plain text (not JSON).";
+
+pub async fn run_agent_loop(
+    provider: &dyn Provider,
+    log_path: &str,
+    mut messages: Vec<Message>,
+) -> Result<String> {
+    for _ in 0..MAX_TOOL_ITERATIONS {
+        let reply = provider.complete(messages.clone()).await?;
+
+        match parse_tool_call(&reply) {
+            Some(call) => {
+                let result = execute_tool(&call, log_path)
+                    .unwrap_or_else(|err| format!("tool error: {}", err));
+                messages.push(Message { role: "assistant".to_string(), content: reply });
+                messages.push(Message { role: "tool".to_string(), content: result });
+            }
+            None => return Ok(reply),
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "AI did not return a final answer within {} tool-call iterations",
+        MAX_TOOL_ITERATIONS
+    ))
+}
+
+fn parse_tool_call(reply: &str) -> Option<ToolCall> {
+    serde_json::from_str(reply.trim()).ok()
+}
+
+fn execute_tool(call: &ToolCall, log_path: &str) -> Result<String> {
+    match call.tool.as_str() {
+        "grep_log" => {
+            let pattern = call.args.get("pattern").and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("grep_log requires a `pattern` argument"))?;
+            let regex = Regex::new(pattern)?;
+            let contents = fs::read_to_string(log_path)?;
+            let hits: Vec<&str> = contents.lines().filter(|line| regex.is_match(line)).collect();
+            Ok(hits.join("\n"))
+        }
+        "get_context" => {
+            let line_no = call.args.get("line_no").and_then(|v| v.as_u64())
+                .ok_or_else(|| anyhow::anyhow!("get_context requires a `line_no` argument"))? as usize;
+            let radius = call.args.get("radius").and_then(|v| v.as_u64()).unwrap_or(3) as usize;
+            let contents = fs::read_to_string(log_path)?;
+            let lines: Vec<&str> = contents.lines().collect();
+            let start = line_no.saturating_sub(radius);
+            let end = (line_no + radius + 1).min(lines.len());
+            Ok(lines.get(start..end).unwrap_or(&[]).join("\n"))
+        }
+        "read_file" => {
+            let path = call.args.get("path").and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("read_file requires a `path` argument"))?;
+            let resolved = resolve_sandboxed_path(path, log_path)?;
+            Ok(fs::read_to_string(resolved)?)
+        }
+        other => Err(anyhow::anyhow!("unknown tool `{}`", other)),
+    }
+}
+
+// Log content is attacker/externally-authored, so a prompt-injected `read_file`
+// call must not be able to reach outside the log's own directory (e.g. `~/.ssh/id_rsa`).
+fn resolve_sandboxed_path(requested: &str, log_path: &str) -> Result<PathBuf> {
+    let root = Path::new(log_path)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let root = root.canonicalize()
+        .with_context(|| format!("Failed to resolve log directory for sandboxing: {}", root.display()))?;
+
+    let resolved = root.join(requested)
+        .canonicalize()
+        .with_context(|| format!("read_file: `{}` does not exist", requested))?;
+
+    if !resolved.starts_with(&root) {
+        return Err(anyhow::anyhow!(
+            "read_file: `{}` escapes the log's directory `{}`, refusing to read it",
+            requested, root.display()
+        ));
+    }
+
+    Ok(resolved)
+}
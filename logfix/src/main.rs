@@ -5,49 +5,127 @@ use clap::{Arg, Command, ArgAction};
 use serde::{Serialize, Deserialize};
 use serde_json;
 use serde_yaml;
-use regex::Regex;
+use regex::{Regex, RegexSet};
 use similar::{TextDiff, Algorithm};
 use colored::*;
-use reqwest::Client;
 use tokio;
 use tiktoken_rs::cl100k_base;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::{thread, time::Duration};
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Utc};
 
-#[derive(Serialize)]
-struct LogOutput {
-    errors: Vec<String>,
-    warnings: Vec<String>,
-    infos: Vec<String>,
-    debugs: Vec<String>,
-    criticals: Vec<String>,
+mod report;
+mod providers;
+mod agent;
+
+use providers::{Message, Provider};
+
+pub(crate) type LogOutput = HashMap<String, Vec<String>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SeverityRule {
+    pub(crate) name: String,
+    pub(crate) pattern: String,
+    #[serde(default)]
+    pub(crate) color: Option<String>,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
 }
 
-#[derive(Serialize)]
-struct OpenAIRequest {
-    model: String,
-    messages: Vec<Message>,
+fn default_severity_rules() -> Vec<SeverityRule> {
+    vec![
+        SeverityRule { name: "ERROR".into(), pattern: r"(?i)error[: ](.*)".into(), color: Some("red".into()), tags: vec![] },
+        SeverityRule { name: "WARNING".into(), pattern: r"(?i)warning[: ](.*)".into(), color: Some("yellow".into()), tags: vec![] },
+        SeverityRule { name: "INFO".into(), pattern: r"(?i)info[: ](.*)".into(), color: Some("blue".into()), tags: vec![] },
+        SeverityRule { name: "DEBUG".into(), pattern: r"(?i)debug[: ](.*)".into(), color: Some("green".into()), tags: vec![] },
+        SeverityRule { name: "CRITICAL".into(), pattern: r"(?i)critical[: ](.*)".into(), color: Some("red".into()), tags: vec![] },
+    ]
 }
 
-#[derive(Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: String,
+fn load_severity_rules(config_path: Option<&str>) -> Result<Vec<SeverityRule>> {
+    let Some(path) = config_path else {
+        return Ok(default_severity_rules());
+    };
+
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read severity rules config: {}", path))?;
+
+    if path.ends_with(".toml") {
+        toml::from_str(&contents).with_context(|| format!("Failed to parse TOML rules config: {}", path))
+    } else {
+        serde_yaml::from_str(&contents).with_context(|| format!("Failed to parse YAML rules config: {}", path))
+    }
 }
 
-#[derive(Deserialize)]
-struct OpenAIResponse {
-    choices: Vec<Choice>,
+struct RuleEngine {
+    rules: Vec<SeverityRule>,
+    set: RegexSet,
+    captures: Vec<Regex>,
+    tag_regex: Regex,
 }
 
-#[derive(Deserialize)]
-struct Choice {
-    message: MessageContent,
+impl RuleEngine {
+    fn new(rules: Vec<SeverityRule>) -> Result<Self> {
+        let set = RegexSet::new(rules.iter().map(|r| &r.pattern))
+            .context("Failed to compile severity rules into a RegexSet")?;
+        let captures = rules.iter()
+            .map(|r| {
+                let regex = Regex::new(&r.pattern)
+                    .with_context(|| format!("Invalid pattern for rule `{}`", r.name))?;
+                if regex.captures_len() < 2 {
+                    return Err(anyhow::anyhow!(
+                        "rule `{}` has pattern `{}` with no capture group; add one (e.g. `(.*)`) to capture the message",
+                        r.name, r.pattern
+                    ));
+                }
+                Ok(regex)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let tag_regex = Regex::new(r"\[(\w+)\]").unwrap();
+
+        Ok(Self { rules, set, captures, tag_regex })
+    }
+
+    fn tag_of(&self, line: &str) -> Option<String> {
+        self.tag_regex.captures(line).map(|cap| cap[1].to_string())
+    }
 }
 
-#[derive(Deserialize)]
-struct MessageContent {
-    content: String,
+#[cfg(test)]
+mod rule_engine_tests {
+    use super::*;
+
+    #[test]
+    fn rejects_a_pattern_with_no_capture_group() {
+        let rules = vec![
+            SeverityRule { name: "FATAL".into(), pattern: "FATAL".into(), color: None, tags: vec![] },
+        ];
+        assert!(RuleEngine::new(rules).is_err());
+    }
+
+    #[test]
+    fn accepts_a_pattern_with_a_capture_group() {
+        let rules = vec![
+            SeverityRule { name: "FATAL".into(), pattern: "FATAL: (.*)".into(), color: None, tags: vec![] },
+        ];
+        assert!(RuleEngine::new(rules).is_ok());
+    }
+}
+
+fn parse_tag_list(arg: Option<&String>) -> Option<Vec<String>> {
+    arg.map(|s| s.split(',').map(|tag| tag.trim().to_string()).collect())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Suggestion {
+    file: String,
+    byte_start: usize,
+    byte_end: usize,
+    replacement: String,
 }
 
 fn show_diff(original: &str, modified: &str) {
@@ -99,46 +177,525 @@ pub fn optimize_log_data(log_data: &str, max_tokens: usize) -> Result<String> {
     Ok(optimized_log)
 }
 
-async fn get_fix_suggestion(log: &str, ai_mode: &str) -> Result<String> {
+async fn get_fix_suggestion(provider: &dyn Provider, log_path: &str, log: &str, ai_mode: &str) -> Result<String> {
     let pb = ProgressBar::new_spinner();
     pb.set_style(ProgressStyle::default_spinner().template("{spinner}  {msg}").unwrap());
     pb.enable_steady_tick(Duration::from_millis(100));
     pb.set_message("Analyzing log with AI...");
 
     let optimized_log = optimize_log_data(log, 4096)?;
-
-    let api_url = "https://api.openai.com/v1/chat/completions";
-    let client = Client::new();
     let prompt = format!(
-        "Analyze the following log file and generate fixes. AI Mode: {}\n\n```log\n{}\n```",
-        ai_mode, optimized_log
+        "Analyze the following log file and generate fixes. AI Mode: {}\n\n{}\n\n```log\n{}\n```",
+        ai_mode, agent::TOOL_INSTRUCTIONS, optimized_log
     );
+    let messages = vec![
+        Message { role: "system".to_string(), content: "You are an expert log analyzer.".to_string() },
+        Message { role: "user".to_string(), content: prompt },
+    ];
+
+    let result = agent::run_agent_loop(provider, log_path, messages).await;
+    pb.finish_with_message("✅ AI Analysis complete!");
+    result
+}
 
-    let response = client.post(api_url)
-        .header("Authorization", format!("Bearer {}", std::env::var("OPENAI_API_KEY")?))
-        .json(&serde_json::json!({
-            "model": "gpt-4",
-            "messages": [{ "role": "system", "content": "You are an expert log analyzer." },
-                         { "role": "user", "content": prompt }],
-            "temperature": 0.7
-        }))
-        .send()
-        .await?;
+async fn get_fix_suggestions_structured(provider: &dyn Provider, log: &str, file_path: &str) -> Result<Vec<Suggestion>> {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner}  {msg}").unwrap());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb.set_message("Asking AI for structured fixes...");
 
+    let optimized_log = optimize_log_data(log, 4096)?;
+    let prompt = format!(
+        "The following log was produced while processing `{}`. {}\n\n\
+         Once you have enough context, reply with ONLY a JSON array of edits, no prose, shaped as \
+         `[{{\"file\": string, \"byte_start\": number, \"byte_end\": number, \"replacement\": string}}]`. \
+         Each entry replaces bytes [byte_start, byte_end) of `file` with `replacement`.\n\n```log\n{}\n```",
+        file_path, agent::TOOL_INSTRUCTIONS, optimized_log
+    );
+    let messages = vec![
+        Message { role: "system".to_string(), content: "You are an expert Rust fixer that only replies with JSON.".to_string() },
+        Message { role: "user".to_string(), content: prompt },
+    ];
+
+    let reply = agent::run_agent_loop(provider, file_path, messages).await?;
     pb.finish_with_message("✅ AI Analysis complete!");
 
-    let response_json = response.json::<OpenAIResponse>().await?;
-    if let Some(choice) = response_json.choices.get(0) {
-        return Ok(choice.message.content.clone());
+    let suggestions: Vec<Suggestion> = serde_json::from_str(&reply)
+        .context("AI reply was not a valid JSON suggestion list")?;
+    Ok(suggestions)
+}
+
+// Suggestion.file comes straight from an AI JSON reply, so it must not be
+// allowed to point anywhere outside the log's own directory before we use it
+// as a read/write target (a prompt-injected reply could otherwise overwrite
+// arbitrary files the process can reach).
+fn resolve_suggestion_path(file: &str, base_dir: &Path) -> Result<PathBuf> {
+    let base_dir = base_dir.canonicalize()
+        .with_context(|| format!("Failed to resolve base directory: {}", base_dir.display()))?;
+
+    let resolved = base_dir.join(file)
+        .canonicalize()
+        .with_context(|| format!("suggestion target `{}` does not exist", file))?;
+
+    if !resolved.starts_with(&base_dir) {
+        return Err(anyhow::anyhow!(
+            "suggestion target `{}` escapes `{}`, refusing to modify it",
+            file, base_dir.display()
+        ));
+    }
+
+    Ok(resolved)
+}
+
+fn apply_suggestions(suggestions: &[Suggestion], base_dir: &Path) -> Result<()> {
+    use std::collections::HashMap;
+
+    let mut by_file: HashMap<&str, Vec<&Suggestion>> = HashMap::new();
+    for s in suggestions {
+        by_file.entry(s.file.as_str()).or_default().push(s);
+    }
+
+    for (file, mut edits) in by_file {
+        let resolved = match resolve_suggestion_path(file, base_dir) {
+            Ok(path) => path,
+            Err(err) => {
+                println!("⚠️  Skipping suggestion(s) for `{}`: {}", file, err);
+                continue;
+            }
+        };
+
+        edits.sort_by(|a, b| b.byte_start.cmp(&a.byte_start));
+
+        for window in edits.windows(2) {
+            let (later, earlier) = (window[0], window[1]);
+            if later.byte_start < earlier.byte_end {
+                return Err(anyhow::anyhow!(
+                    "overlapping suggestions for {}: [{}, {}) and [{}, {})",
+                    file, earlier.byte_start, earlier.byte_end, later.byte_start, later.byte_end
+                ));
+            }
+        }
+
+        let original = fs::read_to_string(&resolved)
+            .with_context(|| format!("Failed to read file for suggestion: {}", resolved.display()))?;
+
+        let mut patched = original.clone();
+        for edit in &edits {
+            if edit.byte_end > patched.len() || edit.byte_start > edit.byte_end {
+                return Err(anyhow::anyhow!("suggestion range out of bounds for {}", resolved.display()));
+            }
+            if !patched.is_char_boundary(edit.byte_start) || !patched.is_char_boundary(edit.byte_end) {
+                return Err(anyhow::anyhow!(
+                    "suggestion range [{}, {}) for {} does not fall on a UTF-8 character boundary",
+                    edit.byte_start, edit.byte_end, resolved.display()
+                ));
+            }
+            patched.replace_range(edit.byte_start..edit.byte_end, &edit.replacement);
+        }
+
+        let resolved_display = resolved.display().to_string();
+        fs::write(format!("{}.bak", resolved_display), &original)
+            .with_context(|| format!("Failed to write backup for {}", resolved_display))?;
+
+        let tmp_path = format!("{}.logfix.tmp", resolved_display);
+        fs::write(&tmp_path, &patched)
+            .with_context(|| format!("Failed to write temp file for {}", resolved_display))?;
+        fs::rename(&tmp_path, &resolved)
+            .with_context(|| format!("Failed to apply fix to {}", resolved_display))?;
+
+        println!("🔧 Applied {} fix(es) to {}", edits.len(), resolved_display);
     }
 
-    Err(anyhow::anyhow!("Failed to get AI-generated fix"))
+    Ok(())
+}
+
+#[cfg(test)]
+mod apply_suggestions_tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("logfix-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn applies_non_overlapping_suggestions_in_any_order() {
+        let dir = temp_dir("apply-ok");
+        let file = dir.join("log.txt");
+        fs::write(&file, "hello world").unwrap();
+
+        let suggestions = vec![
+            Suggestion { file: "log.txt".to_string(), byte_start: 0, byte_end: 5, replacement: "howdy".to_string() },
+            Suggestion { file: "log.txt".to_string(), byte_start: 6, byte_end: 11, replacement: "earth".to_string() },
+        ];
+
+        apply_suggestions(&suggestions, &dir).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "howdy earth");
+    }
+
+    #[test]
+    fn rejects_overlapping_suggestions() {
+        let dir = temp_dir("apply-overlap");
+        let file = dir.join("log.txt");
+        fs::write(&file, "hello world").unwrap();
+
+        let suggestions = vec![
+            Suggestion { file: "log.txt".to_string(), byte_start: 0, byte_end: 6, replacement: "hi ".to_string() },
+            Suggestion { file: "log.txt".to_string(), byte_start: 3, byte_end: 11, replacement: "there".to_string() },
+        ];
+
+        assert!(apply_suggestions(&suggestions, &dir).is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_range() {
+        let dir = temp_dir("apply-bounds");
+        let file = dir.join("log.txt");
+        fs::write(&file, "hello").unwrap();
+
+        let suggestions = vec![
+            Suggestion { file: "log.txt".to_string(), byte_start: 0, byte_end: 50, replacement: "x".to_string() },
+        ];
+
+        assert!(apply_suggestions(&suggestions, &dir).is_err());
+    }
+
+    #[test]
+    fn rejects_non_char_boundary_range() {
+        let dir = temp_dir("apply-boundary");
+        let file = dir.join("log.txt");
+        // "héllo" - the 'é' is a 2-byte UTF-8 character, so byte offset 2 falls mid-character.
+        fs::write(&file, "héllo").unwrap();
+
+        let suggestions = vec![
+            Suggestion { file: "log.txt".to_string(), byte_start: 2, byte_end: 4, replacement: "x".to_string() },
+        ];
+
+        assert!(apply_suggestions(&suggestions, &dir).is_err());
+    }
+
+    #[test]
+    fn skips_suggestion_escaping_base_dir() {
+        let dir = temp_dir("apply-escape");
+        let suggestions = vec![
+            Suggestion { file: "../../../../etc/passwd".to_string(), byte_start: 0, byte_end: 1, replacement: "x".to_string() },
+        ];
+
+        // An escaping suggestion is skipped (not a fatal error) so unrelated suggestions can still apply.
+        assert!(apply_suggestions(&suggestions, &dir).is_ok());
+    }
+}
+
+fn parse_since_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let (value, unit) = input.split_at(
+        input.find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| anyhow::anyhow!("invalid duration `{}`, expected e.g. `15m`", input))?,
+    );
+    let value: u64 = value.parse()
+        .with_context(|| format!("invalid duration `{}`", input))?;
+    let secs = match unit {
+        "s" => value,
+        "m" => value * 60,
+        "h" => value * 60 * 60,
+        "d" => value * 60 * 60 * 24,
+        other => return Err(anyhow::anyhow!("unknown duration unit `{}`, expected s/m/h/d", other)),
+    };
+    Ok(Duration::from_secs(secs))
+}
+
+fn parse_levels_filter(arg: Option<&String>) -> Option<Vec<String>> {
+    arg.map(|s| s.split(',').map(|level| level.trim().to_uppercase()).collect())
+}
+
+fn parse_absolute_time(input: &str) -> Result<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S")
+        .map(|naive| Utc.from_utc_datetime(&naive))
+        .with_context(|| format!("invalid timestamp `{}`, expected RFC3339 or `YYYY-MM-DD HH:MM:SS`", input))
+}
+
+struct TimestampFormats {
+    iso: Regex,
+    syslog: Regex,
+    epoch_millis: Regex,
+}
+
+impl TimestampFormats {
+    fn new() -> Self {
+        Self {
+            iso: Regex::new(r"^(\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)").unwrap(),
+            syslog: Regex::new(r"^([A-Z][a-z]{2}\s+\d{1,2}\s+\d{2}:\d{2}:\d{2})").unwrap(),
+            epoch_millis: Regex::new(r"^(\d{13})\b").unwrap(),
+        }
+    }
+
+    fn parse(&self, line: &str) -> Option<DateTime<Utc>> {
+        if let Some(cap) = self.iso.captures(line) {
+            let matched = &cap[1];
+            if let Ok(dt) = DateTime::parse_from_rfc3339(matched) {
+                return Some(dt.with_timezone(&Utc));
+            }
+            for fmt in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+                if let Ok(naive) = NaiveDateTime::parse_from_str(matched, fmt) {
+                    return Some(Utc.from_utc_datetime(&naive));
+                }
+            }
+        }
+
+        if let Some(cap) = self.syslog.captures(line) {
+            let now = Utc::now();
+            let with_year = format!("{} {}", now.year(), &cap[1]);
+            if let Ok(naive) = NaiveDateTime::parse_from_str(&with_year, "%Y %b %e %H:%M:%S") {
+                let dt = Utc.from_utc_datetime(&naive);
+                // Syslog timestamps carry no year. If the current-year reading lands
+                // more than a day in the future, this is almost certainly a log from
+                // the end of last year read back after the new year rolled over.
+                if dt > now + chrono::Duration::days(1) {
+                    let with_prior_year = format!("{} {}", now.year() - 1, &cap[1]);
+                    if let Ok(naive) = NaiveDateTime::parse_from_str(&with_prior_year, "%Y %b %e %H:%M:%S") {
+                        return Some(Utc.from_utc_datetime(&naive));
+                    }
+                }
+                return Some(dt);
+            }
+        }
+
+        if let Some(cap) = self.epoch_millis.captures(line) {
+            if let Ok(millis) = cap[1].parse::<i64>() {
+                if let Some(dt) = Utc.timestamp_millis_opt(millis).single() {
+                    return Some(dt);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+struct TimestampedLine {
+    line: String,
+    timestamp: Option<DateTime<Utc>>,
+}
+
+// A line with no parseable timestamp of its own inherits the previous line's,
+// so multi-line stack traces stay grouped with their header.
+fn attach_timestamps(lines: Vec<String>) -> Vec<TimestampedLine> {
+    let formats = TimestampFormats::new();
+    let mut previous: Option<DateTime<Utc>> = None;
+
+    lines.into_iter()
+        .map(|line| {
+            let timestamp = formats.parse(&line).or(previous);
+            previous = timestamp;
+            TimestampedLine { line, timestamp }
+        })
+        .collect()
+}
+
+fn filter_by_time_window(
+    entries: Vec<TimestampedLine>,
+    from: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Vec<TimestampedLine> {
+    if from.is_none() && until.is_none() {
+        return entries;
+    }
+
+    entries.into_iter()
+        .filter(|entry| match entry.timestamp {
+            Some(ts) => from.map_or(true, |bound| ts >= bound) && until.map_or(true, |bound| ts <= bound),
+            None => false,
+        })
+        .collect()
+}
+
+fn sort_by_time(mut entries: Vec<TimestampedLine>) -> Vec<TimestampedLine> {
+    entries.sort_by_key(|entry| entry.timestamp);
+    entries
+}
+
+#[cfg(test)]
+mod timestamp_tests {
+    use super::*;
+
+    #[test]
+    fn parses_iso8601_with_offset() {
+        let formats = TimestampFormats::new();
+        let dt = formats.parse("2026-07-26T14:21:34+00:00 some message").unwrap();
+        assert_eq!(dt.year(), 2026);
+    }
+
+    #[test]
+    fn parses_epoch_millis() {
+        let formats = TimestampFormats::new();
+        let dt = formats.parse("1700000000000 startup complete").unwrap();
+        assert_eq!(dt.timestamp_millis(), 1700000000000);
+    }
+
+    #[test]
+    fn parses_syslog_as_current_year_when_not_in_the_future() {
+        let formats = TimestampFormats::new();
+        let label = (Utc::now() - chrono::Duration::days(1)).format("%b %e %H:%M:%S").to_string();
+        let dt = formats.parse(&format!("{} myhost sshd[1]: session opened", label)).unwrap();
+        assert_eq!(dt.year(), Utc::now().year());
+    }
+
+    #[test]
+    fn falls_back_to_prior_year_for_future_looking_syslog_dates() {
+        let formats = TimestampFormats::new();
+        let label = (Utc::now() + chrono::Duration::days(2)).format("%b %e %H:%M:%S").to_string();
+        let dt = formats.parse(&format!("{} myhost sshd[1]: session opened", label)).unwrap();
+        assert_eq!(dt.year(), Utc::now().year() - 1);
+    }
+
+    #[test]
+    fn sorts_entries_chronologically_with_untimestamped_first() {
+        let t1 = Utc.timestamp_opt(1000, 0).single();
+        let t2 = Utc.timestamp_opt(2000, 0).single();
+        let entries = vec![
+            TimestampedLine { line: "b".to_string(), timestamp: t2 },
+            TimestampedLine { line: "none".to_string(), timestamp: None },
+            TimestampedLine { line: "a".to_string(), timestamp: t1 },
+        ];
+
+        let sorted = sort_by_time(entries);
+        let lines: Vec<&str> = sorted.iter().map(|e| e.line.as_str()).collect();
+        assert_eq!(lines, vec!["none", "a", "b"]);
+    }
+}
+
+fn apply_time_filters(contents: &str, from: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>, sort_time: bool) -> String {
+    if detect_log_format(contents) != "plain" || (from.is_none() && until.is_none() && !sort_time) {
+        return contents.to_string();
+    }
+
+    let lines = contents.lines().map(String::from).collect();
+    let mut entries = filter_by_time_window(attach_timestamps(lines), from, until);
+    if sort_time {
+        entries = sort_by_time(entries);
+    }
+
+    entries.into_iter().map(|entry| entry.line).collect::<Vec<_>>().join("\n")
+}
+
+// Falls back to the end of the file if no line in the window is found.
+fn find_follow_start_offset(path: &str, window: Duration) -> Result<u64> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to scan log file for --since: {}", path))?;
+    let cutoff = Utc::now() - chrono::Duration::from_std(window).unwrap();
+    let formats = TimestampFormats::new();
+
+    let mut offset = 0usize;
+    let mut previous: Option<DateTime<Utc>> = None;
+    for line in contents.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let timestamp = formats.parse(trimmed).or(previous);
+        previous = timestamp;
+
+        if timestamp.map_or(false, |ts| ts >= cutoff) {
+            return Ok(offset as u64);
+        }
+
+        offset += line.len();
+    }
+
+    Ok(contents.len() as u64)
+}
+
+// Whether `line` should be emitted by `--follow`: it must match at least one
+// of `engine`'s rules (same RegexSet used by `process_logs_by_level`, so
+// custom `--rules-config` classes work live too), pass the same tag
+// filtering, and if `--levels` was given, match one of the requested rule
+// names.
+fn follow_line_matches(
+    engine: &RuleEngine,
+    line: &str,
+    levels: &Option<Vec<String>>,
+    include_tags: &Option<Vec<String>>,
+    exclude_tags: &Option<Vec<String>>,
+) -> bool {
+    let line_tag = engine.tag_of(line);
+    engine.set.matches(line).into_iter().any(|idx| {
+        let rule = &engine.rules[idx];
+        if !hit_passes_tag_filters(rule, &line_tag, include_tags, exclude_tags) {
+            return false;
+        }
+        match levels {
+            Some(levels) => levels.iter().any(|level| level == &rule.name.to_uppercase()),
+            None => true,
+        }
+    })
+}
+
+fn follow_file(
+    path: &str,
+    since: Option<Duration>,
+    levels: Option<Vec<String>>,
+    engine: &RuleEngine,
+    include_tags: &Option<Vec<String>>,
+    exclude_tags: &Option<Vec<String>>,
+) -> Result<()> {
+    let mut file = fs::File::open(path)
+        .with_context(|| format!("Failed to open log file for --follow: {}", path))?;
+    let metadata = file.metadata()?;
+    let mut inode = metadata.ino();
+
+    let mut pos = match since {
+        Some(window) => find_follow_start_offset(path, window)?,
+        None => metadata.len(),
+    };
+    file.seek(SeekFrom::Start(pos))?;
+
+    println!("👀 Following {} (Ctrl+C to stop)...", path);
+
+    loop {
+        thread::sleep(Duration::from_millis(500));
+
+        let metadata = match fs::metadata(path) {
+            Ok(m) => m,
+            Err(_) => continue, // file momentarily missing during rotation
+        };
+
+        if metadata.ino() != inode {
+            file = fs::File::open(path)
+                .with_context(|| format!("Failed to reopen rotated log file: {}", path))?;
+            inode = metadata.ino();
+            pos = 0;
+        } else if metadata.len() < pos {
+            file.seek(SeekFrom::Start(0))?;
+            pos = 0;
+        }
+
+        let current_len = metadata.len();
+        if current_len > pos {
+            file.seek(SeekFrom::Start(pos))?;
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf)?;
+            pos = current_len;
+
+            let text = String::from_utf8_lossy(&buf);
+            for line in text.lines() {
+                if !follow_line_matches(engine, line, &levels, include_tags, exclude_tags) {
+                    continue;
+                }
+                println!("{}", colorize_log(line));
+            }
+        }
+    }
 }
 
 fn detect_log_format(contents: &str) -> &str {
-    if contents.trim().starts_with('{') {
+    let trimmed = contents.trim();
+    if trimmed.starts_with('{') && trimmed.contains("\"reason\":\"compiler-message\"") {
+        "diagnostics"
+    } else if trimmed.starts_with('{') {
         "json"
-    } else if contents.trim().starts_with("---") || contents.contains(":\n") {
+    } else if trimmed.starts_with("---") || contents.contains(":\n") {
         "yaml"
     } else if contents.contains("=") {
         "toml"
@@ -155,47 +712,166 @@ fn parse_log(contents: &str) -> Vec<String> {
     }
 }
 
-fn process_logs_by_level(contents: &str) -> LogOutput {
-    let error_regex = Regex::new(r"(?i)error[: ](.*)").unwrap();
-    let warning_regex = Regex::new(r"(?i)warning[: ](.*)").unwrap();
-    let info_regex = Regex::new(r"(?i)info[: ](.*)").unwrap();
-    let debug_regex = Regex::new(r"(?i)debug[: ](.*)").unwrap();
-    let critical_regex = Regex::new(r"(?i)critical[: ](.*)").unwrap();
+#[derive(Debug, Clone, Deserialize)]
+struct Span {
+    file_name: String,
+    byte_start: usize,
+    byte_end: usize,
+    #[serde(default)]
+    suggested_replacement: Option<String>,
+    #[serde(default)]
+    suggestion_applicability: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Diagnostic {
+    level: String,
+    message: String,
+    #[serde(default)]
+    spans: Vec<Span>,
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    #[serde(default)]
+    message: Option<Diagnostic>,
+}
+
+fn parse_diagnostics(contents: &str) -> Vec<Diagnostic> {
+    contents.lines()
+        .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+        .filter(|msg| msg.reason == "compiler-message")
+        .filter_map(|msg| msg.message)
+        .collect()
+}
+
+fn process_diagnostics(contents: &str) -> LogOutput {
+    let mut output: LogOutput = HashMap::new();
+    for diagnostic in parse_diagnostics(contents) {
+        output.entry(diagnostic.level.to_uppercase()).or_default().push(diagnostic.message);
+    }
+    output
+}
+
+// Only `MachineApplicable` spans are safe to splice in without human review;
+// `HasPlaceholders`/`MaybeIncorrect`/`Unspecified` suggestions need judgment
+// `cargo fix`/rustfix wouldn't auto-apply either.
+fn suggestions_from_diagnostics(diagnostics: &[Diagnostic]) -> Vec<Suggestion> {
+    diagnostics.iter()
+        .flat_map(|diagnostic| diagnostic.spans.iter())
+        .filter(|span| span.suggestion_applicability.as_deref() == Some("MachineApplicable"))
+        .filter_map(|span| {
+            span.suggested_replacement.as_ref().map(|replacement| Suggestion {
+                file: span.file_name.clone(),
+                byte_start: span.byte_start,
+                byte_end: span.byte_end,
+                replacement: replacement.clone(),
+            })
+        })
+        .collect()
+}
+
+// A hit's tags are the union of its rule's static `tags` and the line's own `[tag]`.
+fn hit_passes_tag_filters(
+    rule: &SeverityRule,
+    line_tag: &Option<String>,
+    include_tags: &Option<Vec<String>>,
+    exclude_tags: &Option<Vec<String>>,
+) -> bool {
+    let effective_tags: Vec<&str> = rule.tags.iter()
+        .map(String::as_str)
+        .chain(line_tag.as_deref())
+        .collect();
+
+    if let Some(include) = include_tags {
+        if !effective_tags.iter().any(|tag| include.iter().any(|i| i == tag)) {
+            return false;
+        }
+    }
+    if let Some(exclude) = exclude_tags {
+        if effective_tags.iter().any(|tag| exclude.iter().any(|e| e == tag)) {
+            return false;
+        }
+    }
+    true
+}
+
+fn process_logs_by_level(
+    contents: &str,
+    engine: &RuleEngine,
+    include_tags: &Option<Vec<String>>,
+    exclude_tags: &Option<Vec<String>>,
+) -> LogOutput {
+    if detect_log_format(contents) == "diagnostics" {
+        return process_diagnostics(contents);
+    }
 
     let lines = parse_log(contents);
-    let (errors, warnings, infos, debugs, criticals) = lines
+
+    lines
         .par_iter()
-        .map(|line| (
-            error_regex.captures(line).map(|cap| cap[1].to_string()),
-            warning_regex.captures(line).map(|cap| cap[1].to_string()),
-            info_regex.captures(line).map(|cap| cap[1].to_string()),
-            debug_regex.captures(line).map(|cap| cap[1].to_string()),
-            critical_regex.captures(line).map(|cap| cap[1].to_string()),
-        ))
+        .map(|line| {
+            let line_tag = engine.tag_of(line);
+            engine.set.matches(line)
+                .into_iter()
+                .filter_map(|idx| {
+                    let rule = &engine.rules[idx];
+                    if !hit_passes_tag_filters(rule, &line_tag, include_tags, exclude_tags) {
+                        return None;
+                    }
+                    engine.captures[idx].captures(line)
+                        .map(|cap| (rule.name.clone(), cap[1].to_string()))
+                })
+                .collect::<Vec<_>>()
+        })
         .fold(
-            || (vec![], vec![], vec![], vec![], vec![]),
-            |mut acc, (e, w, i, d, c)| {
-                if let Some(e) = e { acc.0.push(e); }
-                if let Some(w) = w { acc.1.push(w); }
-                if let Some(i) = i { acc.2.push(i); }
-                if let Some(d) = d { acc.3.push(d); }
-                if let Some(c) = c { acc.4.push(c); }
+            HashMap::new,
+            |mut acc: LogOutput, hits| {
+                for (name, msg) in hits {
+                    acc.entry(name).or_default().push(msg);
+                }
                 acc
             },
         )
         .reduce(
-            || (vec![], vec![], vec![], vec![], vec![]),
-            |mut acc, item| {
-                acc.0.extend(item.0);
-                acc.1.extend(item.1);
-                acc.2.extend(item.2);
-                acc.3.extend(item.3);
-                acc.4.extend(item.4);
+            HashMap::new,
+            |mut acc, other| {
+                for (name, mut msgs) in other {
+                    acc.entry(name).or_default().append(&mut msgs);
+                }
                 acc
             },
-        );
+        )
+}
+
+#[cfg(test)]
+mod tag_filter_tests {
+    use super::*;
+
+    #[test]
+    fn rule_tags_satisfy_include_filter_without_a_line_tag() {
+        let rules = vec![
+            SeverityRule { name: "ERROR".into(), pattern: r"(?i)error[: ](.*)".into(), color: None, tags: vec!["backend".into()] },
+        ];
+        let engine = RuleEngine::new(rules).unwrap();
+        let include = Some(vec!["backend".to_string()]);
+
+        let output = process_logs_by_level("error: disk full", &engine, &include, &None);
+        assert_eq!(output.get("ERROR").unwrap(), &vec!["disk full".to_string()]);
+    }
+
+    #[test]
+    fn rule_tags_are_excluded_even_without_a_line_tag() {
+        let rules = vec![
+            SeverityRule { name: "ERROR".into(), pattern: r"(?i)error[: ](.*)".into(), color: None, tags: vec!["noisy".into()] },
+        ];
+        let engine = RuleEngine::new(rules).unwrap();
+        let exclude = Some(vec!["noisy".to_string()]);
 
-    LogOutput { errors, warnings, infos, debugs, criticals }
+        let output = process_logs_by_level("error: disk full", &engine, &None, &exclude);
+        assert!(output.is_empty());
+    }
 }
 
 #[tokio::main]
@@ -212,6 +888,10 @@ async fn main() -> Result<()> {
                 .value_names(["ORIGINAL_FILE", "FIXED_FILE"])
         )        
         .arg(Arg::new("fix").long("fix").help("Automatically fix errors in the log").action(ArgAction::SetTrue))
+        .arg(Arg::new("apply")
+            .long("apply")
+            .help("Splice AI-suggested fixes back into the source file (implies a preview diff)")
+            .action(ArgAction::SetTrue))
         .arg(Arg::new("fixed")
             .long("fixed")
             .help("Path to fixed log file")
@@ -223,21 +903,114 @@ async fn main() -> Result<()> {
             .long("ai-mode")
             .help("Use advanced AI mode for full log analysis")
             .value_parser(["simple", "full"]))
+        .arg(Arg::new("follow")
+            .long("follow")
+            .help("Tail the log file and classify new lines live, like `tail -f`")
+            .action(ArgAction::SetTrue))
+        .arg(Arg::new("since")
+            .long("since")
+            .help("With --follow, also emit lines from within this long before now (e.g. 15m, 2h)")
+            .num_args(1))
+        .arg(Arg::new("levels")
+            .long("levels")
+            .help("With --follow, only emit lines matching this comma-separated list (e.g. error,critical)")
+            .num_args(1))
+        .arg(Arg::new("rules-config")
+            .long("rules-config")
+            .help("YAML/TOML config of custom severity rules ({ name, pattern, color, tags })")
+            .num_args(1))
+        .arg(Arg::new("include-tag")
+            .long("include-tag")
+            .help("Only keep matches whose [tag] or rule tags are in this comma-separated list")
+            .num_args(1))
+        .arg(Arg::new("exclude-tag")
+            .long("exclude-tag")
+            .help("Drop matches whose [tag] or rule tags are in this comma-separated list")
+            .num_args(1))
+        .arg(Arg::new("report")
+            .long("report")
+            .help("Render a self-contained HTML report (counts, AI suggestion, diff) to this path")
+            .num_args(1))
+        .arg(Arg::new("provider")
+            .long("provider")
+            .help("AI backend to use for fix suggestions")
+            .value_parser(["openai", "anthropic", "ollama"])
+            .default_value("openai"))
+        .arg(Arg::new("model")
+            .long("model")
+            .help("Model name to request from the chosen --provider")
+            .default_value("gpt-4"))
+        .arg(Arg::new("from")
+            .long("from")
+            .help("Only keep entries at or after this time (RFC3339 or `YYYY-MM-DD HH:MM:SS`)")
+            .num_args(1))
+        .arg(Arg::new("until")
+            .long("until")
+            .help("Only keep entries at or before this time (RFC3339 or `YYYY-MM-DD HH:MM:SS`)")
+            .num_args(1))
+        .arg(Arg::new("last")
+            .long("last")
+            .help("Only keep entries within this long before now (e.g. 15m, 2h)")
+            .num_args(1))
+        .arg(Arg::new("sort-time")
+            .long("sort-time")
+            .help("Stably reorder output chronologically instead of by file order")
+            .action(ArgAction::SetTrue))
         .get_matches();
 
+    let severity_rules = load_severity_rules(matches.get_one::<String>("rules-config").map(|s| s.as_str()))?;
+    let rule_engine = RuleEngine::new(severity_rules)?;
+    let include_tags = parse_tag_list(matches.get_one::<String>("include-tag"));
+    let exclude_tags = parse_tag_list(matches.get_one::<String>("exclude-tag"));
+    let provider = providers::build_provider(
+        matches.get_one::<String>("provider").expect("has default"),
+        matches.get_one::<String>("model").expect("has default"),
+    )?;
+
+    let until = matches.get_one::<String>("until").map(|s| parse_absolute_time(s)).transpose()?;
+    let last_bound = matches.get_one::<String>("last")
+        .map(|s| parse_since_duration(s))
+        .transpose()?
+        .map(|window| Utc::now() - chrono::Duration::from_std(window).unwrap());
+    let explicit_from = matches.get_one::<String>("from").map(|s| parse_absolute_time(s)).transpose()?;
+    let from = match (explicit_from, last_bound) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (Some(a), None) => Some(a),
+        (None, Some(b)) => Some(b),
+        (None, None) => None,
+    };
+    let sort_time = matches.get_flag("sort-time");
+
+    // 👀 `--follow` の処理
+    if matches.get_flag("follow") {
+        let file_path = matches.get_one::<String>("file")
+            .ok_or_else(|| anyhow::anyhow!("--follow requires a log file path"))?;
+        let since = matches.get_one::<String>("since")
+            .map(|s| parse_since_duration(s))
+            .transpose()?;
+        let levels = parse_levels_filter(matches.get_one::<String>("levels"));
+        return follow_file(file_path, since, levels, &rule_engine, &include_tags, &exclude_tags);
+    }
+
+    let mut report_log_output: Option<LogOutput> = None;
+    let mut report_ai_suggestion: Option<String> = None;
+    let mut report_diff: Option<(String, String)> = None;
+
     // 🔹 `file` オプションがある場合のみ処理
     if let Some(file_path) = matches.get_one::<String>("file") {
         let log_content = fs::read_to_string(file_path)
             .expect("❌ Failed to read log file.");
-        
-        let log_output = process_logs_by_level(&log_content);
+
+        let time_filtered_content = apply_time_filters(&log_content, from, until, sort_time);
+        let log_output = process_logs_by_level(&time_filtered_content, &rule_engine, &include_tags, &exclude_tags);
 
         // 🧠 AIモードの処理
         if let Some(ai_mode) = matches.get_one::<String>("ai-mode") {
             if ai_mode == "full" {
                 println!("🚀 Running in **FULL AI Mode**: Deep log analysis with improvements...");
-                let fix_suggestion = get_fix_suggestion(&log_content, "full").await?;
+                let fix_suggestion = get_fix_suggestion(provider.as_ref(), file_path, &log_content, "full").await?;
                 println!("📝 AI Analysis:\n{}", fix_suggestion);
+                report_ai_suggestion = Some(fix_suggestion);
             }
         }
 
@@ -247,19 +1020,69 @@ async fn main() -> Result<()> {
             for line in log_content.lines() {
                 println!("{}", colorize_log(line));
             }
-            let fix_suggestion = get_fix_suggestion(&log_content, "simple").await?;
+            let fix_suggestion = get_fix_suggestion(provider.as_ref(), file_path, &log_content, "simple").await?;
             println!("📝 Fixed Log:\n{}", fix_suggestion);
+            report_ai_suggestion = Some(fix_suggestion);
+        }
+
+        // 🩹 `--apply` の処理
+        if matches.get_flag("apply") {
+            let machine_suggestions = if detect_log_format(&log_content) == "diagnostics" {
+                suggestions_from_diagnostics(&parse_diagnostics(&log_content))
+            } else {
+                Vec::new()
+            };
+
+            let suggestions = if !machine_suggestions.is_empty() {
+                println!("🛠 Applying {} compiler-suggested fix(es), no AI round-trip needed", machine_suggestions.len());
+                machine_suggestions
+            } else {
+                get_fix_suggestions_structured(provider.as_ref(), &log_content, file_path).await?
+            };
+
+            let log_dir = Path::new(file_path)
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."));
+
+            if suggestions.is_empty() {
+                println!("✅ AI found nothing to apply.");
+            } else {
+                for suggestion in &suggestions {
+                    let resolved = match resolve_suggestion_path(&suggestion.file, log_dir) {
+                        Ok(path) => path,
+                        Err(err) => {
+                            println!("⚠️  Skipping preview for `{}`: {}", suggestion.file, err);
+                            continue;
+                        }
+                    };
+
+                    let original = fs::read_to_string(&resolved)
+                        .with_context(|| format!("Failed to read file for preview: {}", resolved.display()))?;
+
+                    if suggestion.byte_start > suggestion.byte_end
+                        || suggestion.byte_end > original.len()
+                        || !original.is_char_boundary(suggestion.byte_start)
+                        || !original.is_char_boundary(suggestion.byte_end)
+                    {
+                        println!("⚠️  Skipping preview for `{}`: suggestion range is invalid", suggestion.file);
+                        continue;
+                    }
+
+                    let mut previewed = original.clone();
+                    previewed.replace_range(suggestion.byte_start..suggestion.byte_end, &suggestion.replacement);
+                    println!("📄 Preview of changes to {}:", suggestion.file);
+                    show_diff(&original, &previewed);
+                }
+                apply_suggestions(&suggestions, log_dir)?;
+            }
         }
 
         // 📝 ログ出力処理
-        let log_output_text = format!(
-            "{}\n{}\n{}\n{}\n{}",
-            log_output.errors.join("\n"),
-            log_output.warnings.join("\n"),
-            log_output.infos.join("\n"),
-            log_output.debugs.join("\n"),
-            log_output.criticals.join("\n")
-        );
+        let log_output_text = rule_engine.rules.iter()
+            .map(|rule| log_output.get(&rule.name).map(|lines| lines.join("\n")).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n");
 
         if matches.get_flag("json") {
             println!("{}", serde_json::to_string_pretty(&log_output)?);
@@ -269,10 +1092,12 @@ async fn main() -> Result<()> {
         } else {
             println!("Processed log output.");
         }
+
+        report_log_output = Some(log_output);
     }
 
     // 📄 `--diff` の処理
-    
+
     if let Some(mut files) = matches.get_many::<String>("diff") {
         let original_file = files.next().expect("Missing original file");
         let fixed_file = files.next().expect("Missing fixed file");
@@ -285,6 +1110,20 @@ async fn main() -> Result<()> {
         println!("✅ DEBUG: show_diff() を実行します");
         show_diff(&original_content, &fixed_content);
         println!("✅ DEBUG: show_diff() の処理が終了しました");
+
+        report_diff = Some((original_content, fixed_content));
+    }
+
+    // 📊 `--report` の処理
+    if let Some(report_path) = matches.get_one::<String>("report") {
+        let html = report::render_html(
+            report_log_output.as_ref(),
+            &rule_engine.rules,
+            report_ai_suggestion.as_deref(),
+            report_diff.as_ref().map(|(o, f)| (o.as_str(), f.as_str())),
+        );
+        report::write_report(report_path, &html)?;
+        println!("📊 Wrote HTML report to {}", report_path);
     }
 
     Ok(())
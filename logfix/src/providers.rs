@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: String,
+    pub content: String,
+}
+
+#[async_trait]
+pub trait Provider {
+    async fn complete(&self, messages: Vec<Message>) -> Result<String>;
+}
+
+// Neither OpenAI's `/chat/completions` nor Anthropic's Messages API accept a
+// bare `tool`-role message outside their own native tool-calling schemas,
+// which this agent's plain-text tool protocol (agent.rs) doesn't use. Fold
+// tool results into an ordinary `user` turn instead so both APIs see a plain
+// user/assistant exchange.
+fn fold_tool_role(messages: Vec<Message>) -> Vec<Message> {
+    messages.into_iter()
+        .map(|m| {
+            if m.role == "tool" {
+                Message { role: "user".to_string(), content: format!("Tool result:\n{}", m.content) }
+            } else {
+                m
+            }
+        })
+        .collect()
+}
+
+pub fn build_provider(name: &str, model: &str) -> Result<Box<dyn Provider>> {
+    match name {
+        "openai" => Ok(Box::new(OpenAiProvider { model: model.to_string(), client: Client::new() })),
+        "anthropic" => Ok(Box::new(AnthropicProvider { model: model.to_string(), client: Client::new() })),
+        "ollama" => Ok(Box::new(OllamaProvider {
+            model: model.to_string(),
+            client: Client::new(),
+            host: std::env::var("OLLAMA_HOST").unwrap_or_else(|_| "http://localhost:11434".to_string()),
+        })),
+        other => Err(anyhow::anyhow!("unknown provider `{}`, expected openai/anthropic/ollama", other)),
+    }
+}
+
+pub struct OpenAiProvider {
+    model: String,
+    client: Client,
+}
+
+#[derive(Serialize)]
+struct OpenAIRequest {
+    model: String,
+    messages: Vec<Message>,
+    temperature: f32,
+}
+
+#[derive(Deserialize)]
+struct OpenAIResponse {
+    choices: Vec<OpenAIChoice>,
+}
+
+#[derive(Deserialize)]
+struct OpenAIChoice {
+    message: Message,
+}
+
+#[async_trait]
+impl Provider for OpenAiProvider {
+    async fn complete(&self, messages: Vec<Message>) -> Result<String> {
+        let messages = fold_tool_role(messages);
+        let response = self.client.post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", format!("Bearer {}", std::env::var("OPENAI_API_KEY")?))
+            .json(&OpenAIRequest { model: self.model.clone(), messages, temperature: 0.7 })
+            .send()
+            .await?;
+
+        let response_json = response.json::<OpenAIResponse>().await?;
+        response_json.choices.into_iter().next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| anyhow::anyhow!("Failed to get AI-generated fix"))
+    }
+}
+
+pub struct AnthropicProvider {
+    model: String,
+    client: Client,
+}
+
+#[derive(Serialize)]
+struct AnthropicRequest {
+    model: String,
+    max_tokens: u32,
+    system: String,
+    messages: Vec<Message>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContentBlock>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicContentBlock {
+    text: String,
+}
+
+#[async_trait]
+impl Provider for AnthropicProvider {
+    async fn complete(&self, messages: Vec<Message>) -> Result<String> {
+        let system = messages.iter()
+            .find(|m| m.role == "system")
+            .map(|m| m.content.clone())
+            .unwrap_or_default();
+        let turns = fold_tool_role(messages.into_iter().filter(|m| m.role != "system").collect());
+
+        let response = self.client.post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", std::env::var("ANTHROPIC_API_KEY")?)
+            .header("anthropic-version", "2023-06-01")
+            .json(&AnthropicRequest { model: self.model.clone(), max_tokens: 4096, system, messages: turns })
+            .send()
+            .await?;
+
+        let response_json = response.json::<AnthropicResponse>().await?;
+        response_json.content.into_iter().next()
+            .map(|block| block.text)
+            .ok_or_else(|| anyhow::anyhow!("Failed to get AI-generated fix"))
+    }
+}
+
+pub struct OllamaProvider {
+    model: String,
+    client: Client,
+    host: String,
+}
+
+#[derive(Serialize)]
+struct OllamaRequest {
+    model: String,
+    messages: Vec<Message>,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    message: Message,
+}
+
+#[async_trait]
+impl Provider for OllamaProvider {
+    async fn complete(&self, messages: Vec<Message>) -> Result<String> {
+        let response = self.client.post(format!("{}/api/chat", self.host))
+            .json(&OllamaRequest { model: self.model.clone(), messages, stream: false })
+            .send()
+            .await
+            .context("Failed to reach local Ollama server")?;
+
+        let response_json = response.json::<OllamaResponse>().await?;
+        Ok(response_json.message.content)
+    }
+}
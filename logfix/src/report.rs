@@ -0,0 +1,134 @@
+use crate::{LogOutput, SeverityRule};
+use anyhow::{Context, Result};
+use similar::{Algorithm, ChangeTag, TextDiff};
+use std::fs;
+
+const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Logfix Report</title>
+<style>
+body { font-family: -apple-system, BlinkMacSystemFont, sans-serif; margin: 2rem; background: #111; color: #eee; }
+h1, h2 { font-weight: 600; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1.5rem; }
+th, td { border: 1px solid #444; padding: 0.4rem 0.6rem; text-align: left; }
+details { margin-bottom: 0.5rem; border: 1px solid #333; border-radius: 4px; }
+summary { cursor: pointer; padding: 0.4rem 0.6rem; background: #1b1b1b; }
+pre { white-space: pre-wrap; background: #1b1b1b; padding: 0.5rem; margin: 0; }
+.ai-suggestion { background: #1b1b1b; padding: 1rem; border-radius: 4px; }
+.diff-delete { background: #3a1d1d; color: #ff8080; }
+.diff-insert { background: #1d3a1d; color: #80ff80; }
+.diff-equal { color: #ccc; }
+</style>
+</head>
+<body>
+"#;
+
+const HTML_FOOTER: &str = "</body>\n</html>\n";
+
+pub fn render_html(
+    log_output: Option<&LogOutput>,
+    rules: &[SeverityRule],
+    ai_suggestion: Option<&str>,
+    diff: Option<(&str, &str)>,
+) -> String {
+    let mut html = String::new();
+    html.push_str(HTML_HEADER);
+    html.push_str("<h1>Logfix Report</h1>\n");
+
+    html.push_str("<table class=\"summary\">\n<tr><th>Level</th><th>Count</th></tr>\n");
+    for rule in rules {
+        let count = log_output.and_then(|o| o.get(&rule.name)).map(Vec::len).unwrap_or(0);
+        html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", escape_html(&rule.name), count));
+    }
+    html.push_str("</table>\n");
+
+    if let Some(log_output) = log_output {
+        for rule in rules {
+            let lines = log_output.get(&rule.name).cloned().unwrap_or_default();
+            html.push_str(&format!(
+                "<details class=\"level level-{}\">\n<summary>{} ({})</summary>\n<pre>\n",
+                css_class(&rule.name),
+                escape_html(&rule.name),
+                lines.len()
+            ));
+            for line in &lines {
+                html.push_str(&escape_html(line));
+                html.push('\n');
+            }
+            html.push_str("</pre>\n</details>\n");
+        }
+    }
+
+    if let Some(markdown) = ai_suggestion {
+        html.push_str("<h2>AI Fix Suggestion</h2>\n<div class=\"ai-suggestion\">\n");
+        html.push_str(&markdown_to_html(markdown));
+        html.push_str("</div>\n");
+    }
+
+    if let Some((original, modified)) = diff {
+        html.push_str("<h2>Diff</h2>\n");
+        html.push_str(&render_diff_table(original, modified));
+    }
+
+    html.push_str(HTML_FOOTER);
+    html
+}
+
+// `ai_suggestion` is model output conditioned on attacker-influenceable log
+// content, and this report is meant to be shared, so raw HTML/script blocks
+// that CommonMark would otherwise pass through verbatim are escaped instead
+// of rendered.
+fn markdown_to_html(markdown: &str) -> String {
+    use pulldown_cmark::{html, Event, Parser};
+
+    // `push_html` emits `Event::Html`/`Event::InlineHtml` verbatim but HTML-escapes
+    // `Event::Text`, so the escaped string must stay an Html event or it gets
+    // escaped a second time (`<script>` -> `&lt;script&gt;` -> `&amp;lt;...`).
+    let parser = Parser::new(markdown).map(|event| match event {
+        Event::Html(raw) => Event::Html(escape_html(&raw).into()),
+        Event::InlineHtml(raw) => Event::InlineHtml(escape_html(&raw).into()),
+        other => other,
+    });
+
+    let mut out = String::new();
+    html::push_html(&mut out, parser);
+    out
+}
+
+fn render_diff_table(original: &str, modified: &str) -> String {
+    let diff = TextDiff::configure().algorithm(Algorithm::Myers).diff_lines(original, modified);
+
+    let mut rows = String::new();
+    for change in diff.iter_all_changes() {
+        let (css, left, right) = match change.tag() {
+            ChangeTag::Delete => ("diff-delete", change.to_string(), String::new()),
+            ChangeTag::Insert => ("diff-insert", String::new(), change.to_string()),
+            ChangeTag::Equal => ("diff-equal", change.to_string(), change.to_string()),
+        };
+        rows.push_str(&format!(
+            "<tr class=\"{}\"><td>{}</td><td>{}</td></tr>\n",
+            css,
+            escape_html(&left),
+            escape_html(&right)
+        ));
+    }
+
+    format!(
+        "<table class=\"diff\">\n<tr><th>Original</th><th>Fixed</th></tr>\n{}</table>\n",
+        rows
+    )
+}
+
+fn css_class(name: &str) -> String {
+    name.to_lowercase().replace(|c: char| !c.is_ascii_alphanumeric(), "-")
+}
+
+fn escape_html(input: &str) -> String {
+    input.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub fn write_report(path: &str, html: &str) -> Result<()> {
+    fs::write(path, html).with_context(|| format!("Failed to write HTML report to {}", path))
+}